@@ -4,14 +4,24 @@ use winapi::shared::minwindef::BYTE;
 use winapi::shared::minwindef::DWORD;
 use winapi::shared::minwindef::{LPARAM, LPDWORD};
 use winapi::shared::windef::{HDC, HMONITOR, LPRECT};
-use winapi::um::lowlevelmonitorconfigurationapi::SetVCPFeature;
-use winapi::um::lowlevelmonitorconfigurationapi::{
-    CapabilitiesRequestAndCapabilitiesReply, GetCapabilitiesStringLength,
-};
 use winapi::um::physicalmonitorenumerationapi::{
     GetNumberOfPhysicalMonitorsFromHMONITOR, GetPhysicalMonitorsFromHMONITOR, PHYSICAL_MONITOR,
 };
-use winapi::um::winuser::EnumDisplayMonitors;
+use winapi::um::wingdi::{
+    DISPLAY_DEVICE_ACTIVE, DISPLAY_DEVICE_MIRRORING_DRIVER, DISPLAY_DEVICE_PRIMARY_DEVICE,
+};
+use winapi::um::wingdi::{
+    DEVMODEW, DM_BITSPERPEL, DM_DISPLAYFREQUENCY, DM_PELSHEIGHT, DM_PELSWIDTH,
+};
+use winapi::um::winuser::{
+    ChangeDisplaySettingsExW, EnumDisplayDevicesW, EnumDisplayMonitors, EnumDisplaySettingsExW,
+    GetMonitorInfoW, DISPLAY_DEVICEW, ENUM_CURRENT_SETTINGS, MONITORINFO, MONITORINFOEXW,
+};
+
+use winapi::um::lowlevelmonitorconfigurationapi::{
+    CapabilitiesRequestAndCapabilitiesReply, GetCapabilitiesStringLength,
+    GetVCPFeatureAndVCPFeatureReply, SetVCPFeature, MC_VCP_CODE_TYPE,
+};
 
 #[derive(Debug, Clone)]
 pub struct MonitorError(&'static str);
@@ -81,26 +91,162 @@ impl fmt::Display for MonitorInput {
     }
 }
 
-#[derive(Default)]
-pub struct Monitor {
-    pub cap_string: Option<String>,
-    pub capabilities: Option<MonitorCapabilities>,
-    pub phys_mons: PHYSICAL_MONITOR,
-    pub inputs: Vec<MonitorInput>,
+impl MonitorInput {
+    /// Parse a variant name as written in a profile file (e.g. `HDMI1`,
+    /// `DisplayPort1`). Returns `None` for anything unrecognised.
+    pub fn from_name(name: &str) -> Option<MonitorInput> {
+        use MonitorInput::*;
+
+        let input = match name {
+            "AnalogVideo1" => AnalogVideo1,
+            "AnalogVideo2" => AnalogVideo2,
+            "DVI1" => DVI1,
+            "DVI2" => DVI2,
+            "CompositeVideo1" => CompositeVideo1,
+            "CompositeVideo2" => CompositeVideo2,
+            "SVideo1" => SVideo1,
+            "SVideo2" => SVideo2,
+            "Tuner1" => Tuner1,
+            "Tuner2" => Tuner2,
+            "Tuner3" => Tuner3,
+            "ComponentVideo1" => ComponentVideo1,
+            "ComponentVideo2" => ComponentVideo2,
+            "ComponentVideo3" => ComponentVideo3,
+            "DisplayPort1" => DisplayPort1,
+            "DisplayPort2" => DisplayPort2,
+            "HDMI1" => HDMI1,
+            "HDMI2" => HDMI2,
+            _ => return None,
+        };
+
+        Some(input)
+    }
 }
 
-impl fmt::Display for Monitor {
+/// Continuous VCP controls exposed in the MCCS capabilities string.
+/// Unlike `MonitorInput` (code 0x60), these range from 0 to a monitor
+/// reported maximum and are nudged up and down from the tray.
+#[derive(Debug, Copy, Clone)]
+pub enum ContinuousControl {
+    Brightness,
+    Contrast,
+    Volume,
+}
+
+impl ContinuousControl {
+    pub const ALL: [ContinuousControl; 3] = [
+        ContinuousControl::Brightness,
+        ContinuousControl::Contrast,
+        ContinuousControl::Volume,
+    ];
+
+    /// The VCP code driven on the wire.
+    fn code(&self) -> BYTE {
+        match self {
+            ContinuousControl::Brightness => 0x10,
+            ContinuousControl::Contrast => 0x12,
+            ContinuousControl::Volume => 0x62,
+        }
+    }
+
+    /// The code as it appears in `MonitorCapabilities.vcp_codes`.
+    fn cap_code(&self) -> &'static str {
+        match self {
+            ContinuousControl::Brightness => "10",
+            ContinuousControl::Contrast => "12",
+            ContinuousControl::Volume => "62",
+        }
+    }
+}
+
+impl fmt::Display for ContinuousControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            ContinuousControl::Brightness => "Brightness",
+            ContinuousControl::Contrast => "Contrast",
+            ContinuousControl::Volume => "Volume",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+/// A display mode reported by the GDI for a device: pixel dimensions,
+/// refresh rate in Hz and colour depth in bits per pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoMode {
+    pub size: (u32, u32),
+    pub refresh_rate: u32,
+    pub bit_depth: u32,
+}
+
+impl fmt::Display for VideoMode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "<{}>", "Monitor")
+        write!(
+            f,
+            "{}x{} @ {}Hz ({}-bit)",
+            self.size.0, self.size.1, self.refresh_rate, self.bit_depth
+        )
     }
 }
 
-impl Monitor {
-    fn set_vcp_feature(&self, code: BYTE, new_value: DWORD) -> Result<(), MonitorError> {
-        let hmonitor = self.phys_mons.hPhysicalMonitor;
+/// A single VCP feature reading: the current value and the maximum the
+/// monitor will accept for that code.
+pub struct VcpValue {
+    pub current: DWORD,
+    pub maximum: DWORD,
+}
+
+/// The DDC/CI operations we need from a monitor, mirroring the `ddc`
+/// crate's `Ddc` trait. Implemented by the winapi backend for real
+/// hardware and by `MockDdc` so the tray logic can run without a monitor.
+pub trait Ddc {
+    fn get_vcp_feature(&self, code: BYTE) -> Result<VcpValue, MonitorError>;
+    fn set_vcp_feature(&self, code: BYTE, value: DWORD) -> Result<(), MonitorError>;
+    fn capabilities_string(&self) -> Result<Vec<u8>, MonitorError>;
+}
+
+/// DDC backend driving a physical monitor through the Windows
+/// low-level monitor configuration API.
+pub struct WinApiDdc {
+    phys_mon: PHYSICAL_MONITOR,
+}
+
+impl WinApiDdc {
+    pub fn new(phys_mon: PHYSICAL_MONITOR) -> WinApiDdc {
+        WinApiDdc { phys_mon }
+    }
+}
+
+impl Ddc for WinApiDdc {
+    fn get_vcp_feature(&self, code: BYTE) -> Result<VcpValue, MonitorError> {
+        let hmonitor = self.phys_mon.hPhysicalMonitor;
 
         unsafe {
-            let result = SetVCPFeature(hmonitor, code, new_value);
+            let mut code_type: MC_VCP_CODE_TYPE = 0;
+            let mut current: DWORD = 0;
+            let mut maximum: DWORD = 0;
+
+            let result = GetVCPFeatureAndVCPFeatureReply(
+                hmonitor,
+                code,
+                &mut code_type,
+                &mut current,
+                &mut maximum,
+            );
+
+            return match result {
+                0 => Err(MonitorError("Failed to get value for monitor")),
+                _ => Ok(VcpValue { current, maximum }),
+            };
+        }
+    }
+
+    fn set_vcp_feature(&self, code: BYTE, value: DWORD) -> Result<(), MonitorError> {
+        let hmonitor = self.phys_mon.hPhysicalMonitor;
+
+        unsafe {
+            let result = SetVCPFeature(hmonitor, code, value);
 
             return match result {
                 1 => Ok(()),
@@ -109,14 +255,241 @@ impl Monitor {
         }
     }
 
-    pub fn set_input(&self, input: MonitorInput) -> Result<(), MonitorError> {
-        let code = 60; // Input Select VCP Code
-        let input_code = input as u8;
+    fn capabilities_string(&self) -> Result<Vec<u8>, MonitorError> {
+        unsafe {
+            let mut cap_string_len: DWORD = 0;
+            GetCapabilitiesStringLength(self.phys_mon.hPhysicalMonitor, &mut cap_string_len);
 
-        println!("{:?}", input);
+            let mut cap_string_buf: Vec<i8> = vec![0; cap_string_len as usize];
+
+            let result = CapabilitiesRequestAndCapabilitiesReply(
+                self.phys_mon.hPhysicalMonitor,
+                cap_string_buf.as_mut_ptr(),
+                cap_string_len,
+            );
+
+            if result == 0 {
+                return Err(MonitorError("Failed to read capabilities string"));
+            }
+
+            Ok(cap_string_buf.iter().map(|&c| c as u8).collect())
+        }
+    }
+}
+
+/// In-memory DDC backend for tests: hands back a canned capabilities
+/// string and records VCP writes so assertions can inspect them.
+pub struct MockDdc {
+    pub capabilities: Vec<u8>,
+    pub values: std::cell::RefCell<std::collections::HashMap<BYTE, VcpValue>>,
+}
+
+impl MockDdc {
+    pub fn new(capabilities: &str) -> MockDdc {
+        MockDdc {
+            capabilities: capabilities.as_bytes().to_vec(),
+            values: std::cell::RefCell::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Seed a code's current and maximum, as a real monitor would report
+    /// them, so tests can drive clamping against a known range.
+    pub fn seed(&self, code: BYTE, current: DWORD, maximum: DWORD) {
+        self.values
+            .borrow_mut()
+            .insert(code, VcpValue { current, maximum });
+    }
+}
+
+impl Ddc for MockDdc {
+    fn get_vcp_feature(&self, code: BYTE) -> Result<VcpValue, MonitorError> {
+        let values = self.values.borrow();
+
+        match values.get(&code) {
+            Some(v) => Ok(VcpValue {
+                current: v.current,
+                maximum: v.maximum,
+            }),
+            None => Ok(VcpValue {
+                current: 0,
+                maximum: 0,
+            }),
+        }
+    }
+
+    fn set_vcp_feature(&self, code: BYTE, value: DWORD) -> Result<(), MonitorError> {
+        let mut values = self.values.borrow_mut();
+
+        let maximum = values.get(&code).map(|v| v.maximum).unwrap_or(0);
+
+        values.insert(
+            code,
+            VcpValue {
+                current: value,
+                maximum,
+            },
+        );
 
         Ok(())
     }
+
+    fn capabilities_string(&self) -> Result<Vec<u8>, MonitorError> {
+        Ok(self.capabilities.clone())
+    }
+}
+
+pub struct Monitor {
+    pub cap_string: Option<String>,
+    pub capabilities: Option<MonitorCapabilities>,
+    pub backend: Box<dyn Ddc>,
+    pub inputs: Vec<MonitorInput>,
+    pub name: String,
+    pub is_primary: bool,
+    pub device_name: String,
+}
+
+impl fmt::Display for Monitor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}>", self.name)
+    }
+}
+
+/// Friendly name and primary flag resolved from `EnumDisplayDevicesW`,
+/// keyed by the GDI device name (e.g. `\\.\DISPLAY1`).
+#[derive(Default, Clone)]
+struct DisplayDevice {
+    name: String,
+    is_primary: bool,
+}
+
+/// Read a null-terminated UTF-16 Win32 string field into a `String`.
+fn wide_to_string(wide: &[u16]) -> String {
+    let len = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..len])
+}
+
+/// Encode a `&str` as a null-terminated UTF-16 buffer for Win32 calls.
+fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+fn devmode_to_video_mode(devmode: &DEVMODEW) -> VideoMode {
+    VideoMode {
+        size: (devmode.dmPelsWidth, devmode.dmPelsHeight),
+        refresh_rate: devmode.dmDisplayFrequency,
+        bit_depth: devmode.dmBitsPerPel,
+    }
+}
+
+/// Switch the given GDI device to `mode` via `ChangeDisplaySettingsExW`.
+/// Lives as a free function so tray callbacks can apply a mode without
+/// holding a borrow of the `MonitorManager`.
+pub fn set_video_mode(device_name: &str, mode: &VideoMode) -> Result<(), MonitorError> {
+    let mut device = to_wide(device_name);
+
+    unsafe {
+        let mut devmode: DEVMODEW = std::mem::zeroed();
+        devmode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+        devmode.dmPelsWidth = mode.size.0;
+        devmode.dmPelsHeight = mode.size.1;
+        devmode.dmDisplayFrequency = mode.refresh_rate;
+        devmode.dmBitsPerPel = mode.bit_depth;
+        devmode.dmFields =
+            DM_PELSWIDTH | DM_PELSHEIGHT | DM_DISPLAYFREQUENCY | DM_BITSPERPEL;
+
+        let result = ChangeDisplaySettingsExW(
+            device.as_mut_ptr(),
+            &mut devmode,
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+        );
+
+        match result {
+            0 => Ok(()),
+            _ => Err(MonitorError("Failed to change display settings")),
+        }
+    }
+}
+
+impl Monitor {
+    pub fn current_input(&self) -> Result<MonitorInput, MonitorError> {
+        let value = self.backend.get_vcp_feature(0x60)?;
+
+        use num_traits::FromPrimitive;
+        Ok(MonitorInput::from_u32(value.current).unwrap_or(MonitorInput::Unknown))
+    }
+
+    pub fn set_input(&self, input: MonitorInput) -> Result<MonitorInput, MonitorError> {
+        self.backend.set_vcp_feature(0x60, input as DWORD)?; // Input Select VCP Code
+
+        self.current_input()
+    }
+
+    /// The continuous controls this monitor advertises in its
+    /// capabilities string, in a stable order.
+    pub fn continuous_controls(&self) -> Vec<ContinuousControl> {
+        let caps = match &self.capabilities {
+            Some(caps) => caps,
+            None => return vec![],
+        };
+
+        ContinuousControl::ALL
+            .iter()
+            .cloned()
+            .filter(|control| {
+                caps.vcp_codes
+                    .iter()
+                    .any(|cmd| cmd.command == control.cap_code())
+            })
+            .collect()
+    }
+
+    pub fn get_continuous(&self, control: ContinuousControl) -> Result<VcpValue, MonitorError> {
+        self.backend.get_vcp_feature(control.code())
+    }
+
+    /// Set a continuous control to an absolute value, clamped to the
+    /// monitor's reported maximum. A reported maximum of 0 is treated as
+    /// unknown and the value is written unclamped.
+    pub fn set_continuous(
+        &self,
+        control: ContinuousControl,
+        value: DWORD,
+    ) -> Result<(), MonitorError> {
+        let current = self.backend.get_vcp_feature(control.code())?;
+
+        let clamped = if current.maximum != 0 && value > current.maximum {
+            current.maximum
+        } else {
+            value
+        };
+
+        self.backend.set_vcp_feature(control.code(), clamped)
+    }
+
+    /// Nudge a continuous control by `delta`, clamped to `[0, maximum]`
+    /// (only the lower bound applies when the reported maximum is 0),
+    /// and return the re-read value so the caller sees the real state.
+    pub fn nudge(
+        &self,
+        control: ContinuousControl,
+        delta: i32,
+    ) -> Result<VcpValue, MonitorError> {
+        let value = self.backend.get_vcp_feature(control.code())?;
+
+        let mut next = value.current as i32 + delta;
+        if next < 0 {
+            next = 0;
+        }
+        if value.maximum != 0 && next > value.maximum as i32 {
+            next = value.maximum as i32;
+        }
+
+        self.backend.set_vcp_feature(control.code(), next as DWORD)?;
+
+        self.backend.get_vcp_feature(control.code())
+    }
 }
 
 pub struct MonitorManager {
@@ -155,6 +528,79 @@ impl MonitorManager {
         }
     }
 
+    fn enumerate_display_devices(&self) -> std::collections::HashMap<String, DisplayDevice> {
+        let mut devices = std::collections::HashMap::new();
+
+        let mut index: DWORD = 0;
+
+        loop {
+            let mut device: DISPLAY_DEVICEW = unsafe { std::mem::zeroed() };
+            device.cb = std::mem::size_of::<DISPLAY_DEVICEW>() as DWORD;
+
+            let result =
+                unsafe { EnumDisplayDevicesW(std::ptr::null(), index, &mut device, 0) };
+
+            if result == 0 {
+                break;
+            }
+
+            index += 1;
+
+            let flags = device.StateFlags;
+
+            if flags & DISPLAY_DEVICE_MIRRORING_DRIVER != 0 {
+                continue;
+            }
+
+            if flags & DISPLAY_DEVICE_ACTIVE == 0 {
+                continue;
+            }
+
+            let device_name = wide_to_string(&device.DeviceName);
+
+            // The adapter's `DeviceString` is the GPU description and is
+            // identical for every monitor on the same adapter, so take a
+            // second pass keyed on the adapter name to read the monitor's
+            // own friendly name, falling back to the adapter string.
+            let mut monitor: DISPLAY_DEVICEW = unsafe { std::mem::zeroed() };
+            monitor.cb = std::mem::size_of::<DISPLAY_DEVICEW>() as DWORD;
+
+            let monitor_result = unsafe {
+                EnumDisplayDevicesW(device.DeviceName.as_ptr(), 0, &mut monitor, 0)
+            };
+
+            let name = if monitor_result != 0 && monitor.DeviceString[0] != 0 {
+                wide_to_string(&monitor.DeviceString)
+            } else {
+                wide_to_string(&device.DeviceString)
+            };
+
+            devices.insert(
+                device_name,
+                DisplayDevice {
+                    name,
+                    is_primary: flags & DISPLAY_DEVICE_PRIMARY_DEVICE != 0,
+                },
+            );
+        }
+
+        devices
+    }
+
+    fn get_monitor_device_name(&self, hmonitor: HMONITOR) -> Option<String> {
+        let mut info: MONITORINFOEXW = unsafe { std::mem::zeroed() };
+        info.cbSize = std::mem::size_of::<MONITORINFOEXW>() as DWORD;
+
+        let result =
+            unsafe { GetMonitorInfoW(hmonitor, &mut info as *mut _ as *mut MONITORINFO) };
+
+        if result == 0 {
+            return None;
+        }
+
+        Some(wide_to_string(&info.szDevice))
+    }
+
     fn get_number_of_physical_monitors_from_hmonitor(&self, hmonitor: HMONITOR) -> i32 {
         let mut num_phys_monitors: Box<i32> = Box::new(0);
 
@@ -189,51 +635,17 @@ impl MonitorManager {
         }
     }
 
-    fn get_capabilities_string_length(&self, phys_mon: PHYSICAL_MONITOR) -> i32 {
-        let mut cap_string_len: Box<i32> = Box::new(0);
-
-        unsafe {
-            let cap_len_ptr = Box::into_raw(cap_string_len);
-            let cap_lpdword: LPDWORD = std::mem::transmute(cap_len_ptr);
-
-            GetCapabilitiesStringLength(phys_mon.hPhysicalMonitor, cap_lpdword);
-
-            cap_string_len = Box::from_raw(cap_len_ptr);
-
-            return *cap_string_len;
-        }
-    }
-
-    fn capabilities_request_and_capabilities_reply(
-        &self,
-        phys_mon: PHYSICAL_MONITOR,
-        cap_string_len: i32,
-    ) -> String {
-        unsafe {
-            let mut cap_string_buf: Vec<i8> = vec![0; cap_string_len as usize];
-
-            CapabilitiesRequestAndCapabilitiesReply(
-                phys_mon.hPhysicalMonitor,
-                cap_string_buf.as_mut_ptr(),
-                cap_string_len as u32,
-            );
-
-            let cap_string =
-                String::from_utf8(cap_string_buf.iter().map(|&c| c as u8).collect()).unwrap();
-
-            return String::from(cap_string.trim_matches(char::from(0)));
-        }
-    }
-
     pub fn get_all_inputs_for_monitor(
         &self,
         capabilities: &MonitorCapabilities,
     ) -> Result<Vec<MonitorInput>, MonitorError> {
-        let input_select_values = capabilities
+        let input_select = capabilities
             .vcp_codes
             .iter()
             .find(|cmd| &cmd.command == "60")
-            .unwrap()
+            .ok_or(MonitorError("Monitor does not expose input select (0x60)"))?;
+
+        let input_select_values = input_select
             .values
             .iter()
             .map(|v| match &v.command[..] {
@@ -262,42 +674,117 @@ impl MonitorManager {
         Ok(input_select_values)
     }
 
+    /// The mode the device is currently running, via
+    /// `EnumDisplaySettingsExW(ENUM_CURRENT_SETTINGS)`.
+    pub fn current_video_mode(&self, device_name: &str) -> Option<VideoMode> {
+        let device = to_wide(device_name);
+
+        unsafe {
+            let mut devmode: DEVMODEW = std::mem::zeroed();
+            devmode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+
+            let result = EnumDisplaySettingsExW(
+                device.as_ptr(),
+                ENUM_CURRENT_SETTINGS,
+                &mut devmode,
+                0,
+            );
+
+            if result == 0 {
+                return None;
+            }
+
+            Some(devmode_to_video_mode(&devmode))
+        }
+    }
+
+    /// All distinct modes the device advertises, walked by increasing
+    /// `iModeNum` until `EnumDisplaySettingsExW` returns 0.
+    pub fn enumerate_video_modes(&self, device_name: &str) -> Vec<VideoMode> {
+        let device = to_wide(device_name);
+
+        let mut modes: Vec<VideoMode> = vec![];
+        let mut mode_num: DWORD = 0;
+
+        unsafe {
+            loop {
+                let mut devmode: DEVMODEW = std::mem::zeroed();
+                devmode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+
+                let result =
+                    EnumDisplaySettingsExW(device.as_ptr(), mode_num, &mut devmode, 0);
+
+                if result == 0 {
+                    break;
+                }
+
+                mode_num += 1;
+
+                let mode = devmode_to_video_mode(&devmode);
+
+                if !modes.contains(&mode) {
+                    modes.push(mode);
+                }
+            }
+        }
+
+        modes
+    }
+
     pub fn get_all_monitors(&self) -> Result<Vec<Monitor>, MonitorError> {
         let display_mons = self.enum_display_monitors();
+        let devices = self.enumerate_display_devices();
 
         let mut monitors: Vec<Monitor> = vec![];
 
         for mon_ref in display_mons {
+            let gdi_name = self.get_monitor_device_name(mon_ref).unwrap_or_default();
+            let device = devices.get(&gdi_name).cloned().unwrap_or_default();
+
             let phys_num = self.get_number_of_physical_monitors_from_hmonitor(mon_ref);
             let phys_mons = self.get_physical_monitors_from_hmonitor(mon_ref, phys_num);
 
             for phys_mon in phys_mons {
-                let mut mon = Monitor {
-                    ..Default::default()
-                };
+                let backend = WinApiDdc::new(phys_mon);
 
-                let cap_str_len = self.get_capabilities_string_length(phys_mon);
+                let cap_bytes = match backend.capabilities_string() {
+                    Ok(bytes) => bytes,
+                    Err(_) => continue,
+                };
 
-                let cap_reply_str =
-                    self.capabilities_request_and_capabilities_reply(phys_mon, cap_str_len);
+                let cap_reply_str = match String::from_utf8(cap_bytes) {
+                    Ok(string) => string,
+                    Err(_) => continue,
+                };
+                let cap_reply_str = String::from(cap_reply_str.trim_matches(char::from(0)));
 
                 if cap_reply_str.is_empty() || cap_reply_str.eq("") {
                     continue;
                 }
 
-                mon.cap_string = Some(cap_reply_str.clone());
-
-                let caps = MonitorCapabilities::from_cap_string(cap_reply_str);
+                let caps = MonitorCapabilities::from_cap_string(cap_reply_str.clone());
                 match caps {
                     Ok(result) => {
-                        mon.phys_mons = phys_mon;
-
-                        let inputs = self.get_all_inputs_for_monitor(&result).unwrap();
-
-                        println!("monitors: {:?}", inputs);
-
-                        mon.capabilities = Some(result);
-                        mon.inputs = inputs;
+                        let inputs = match self.get_all_inputs_for_monitor(&result) {
+                            Ok(inputs) => inputs,
+                            Err(_) => continue,
+                        };
+
+                        let name = if device.name.is_empty() {
+                            result.display_model.clone()
+                        } else {
+                            device.name.clone()
+                        };
+
+                        let mon = Monitor {
+                            cap_string: Some(cap_reply_str),
+                            capabilities: Some(result),
+                            backend: Box::new(backend),
+                            inputs,
+                            name,
+                            is_primary: device.is_primary,
+                            device_name: gdi_name.clone(),
+                        };
 
                         monitors.push(mon);
                     }
@@ -323,3 +810,66 @@ unsafe extern "system" fn lpfn_enum_callback(
 
     return 1;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor_with(backend: MockDdc) -> Monitor {
+        Monitor {
+            cap_string: None,
+            capabilities: None,
+            backend: Box::new(backend),
+            inputs: vec![],
+            name: String::from("Mock"),
+            is_primary: false,
+            device_name: String::new(),
+        }
+    }
+
+    #[test]
+    fn mock_preserves_maximum_across_writes() {
+        let mock = MockDdc::new("");
+        mock.seed(0x10, 50, 100);
+
+        mock.set_vcp_feature(0x10, 70).unwrap();
+
+        let value = mock.get_vcp_feature(0x10).unwrap();
+        assert_eq!(value.current, 70);
+        assert_eq!(value.maximum, 100);
+    }
+
+    #[test]
+    fn set_input_round_trips_through_0x60() {
+        let monitor = monitor_with(MockDdc::new(""));
+
+        let written = monitor.set_input(MonitorInput::HDMI1).unwrap();
+        assert_eq!(written as u8, MonitorInput::HDMI1 as u8);
+        assert_eq!(
+            monitor.current_input().unwrap() as u8,
+            MonitorInput::HDMI1 as u8
+        );
+    }
+
+    #[test]
+    fn nudge_clamps_to_reported_maximum() {
+        let mock = MockDdc::new("");
+        mock.seed(ContinuousControl::Brightness.code(), 95, 100);
+
+        let monitor = monitor_with(mock);
+        let value = monitor.nudge(ContinuousControl::Brightness, 10).unwrap();
+
+        assert_eq!(value.current, 100);
+    }
+
+    #[test]
+    fn nudge_without_reported_maximum_is_not_forced_to_zero() {
+        let mock = MockDdc::new("");
+        mock.seed(ContinuousControl::Volume.code(), 20, 0);
+
+        let monitor = monitor_with(mock);
+        let value = monitor.nudge(ContinuousControl::Volume, 5).unwrap();
+
+        assert_eq!(value.current, 25);
+    }
+}
@@ -1,5 +1,12 @@
-use crate::display::monitor::{Monitor, MonitorManager};
+use crate::display::monitor::{
+    set_video_mode, ContinuousControl, Monitor, MonitorInput, MonitorManager,
+};
+use std::collections::HashMap;
 use systray;
+use winapi::um::winuser::{
+    DispatchMessageW, GetMessageW, RegisterHotKey, TranslateMessage, MOD_ALT, MOD_CONTROL,
+    MOD_NOREPEAT, MSG, WM_HOTKEY,
+};
 
 #[derive(Debug, Clone)]
 pub struct AppError(&'static str);
@@ -16,9 +23,116 @@ impl std::error::Error for AppError {
     }
 }
 
+/// A named set of per-monitor targets applied in one shot. Monitors are
+/// matched by their resolved friendly name (`Monitor::name`), which keeps
+/// a profile working across reboots as `HMONITOR` handles change.
+///
+/// Limitation: the friendly name comes from `EnumDisplayDevicesW`, so two
+/// monitors of the same model report the same string and cannot be told
+/// apart here — they would share a profile entry. Disambiguating those
+/// needs a per-monitor unique key (EDID), which we do not read yet. Only
+/// input and brightness are modelled; the "resolution" part of the
+/// original request is not implemented (use the video-mode submenu).
+#[derive(Clone)]
+pub struct Profile {
+    pub name: String,
+    pub inputs: HashMap<String, MonitorInput>,
+    pub brightness: HashMap<String, u32>,
+}
+
+impl Profile {
+    fn apply(&self, manager: &MonitorManager) {
+        for monitor in &manager.monitors {
+            if let Some(input) = self.inputs.get(&monitor.name) {
+                monitor.set_input(*input).ok();
+            }
+
+            if let Some(level) = self.brightness.get(&monitor.name) {
+                monitor
+                    .set_continuous(ContinuousControl::Brightness, *level)
+                    .ok();
+            }
+        }
+    }
+}
+
+/// Load profiles from `profiles.ini` in the working directory, returning
+/// an empty list if it is missing or unreadable. The format is ini-like:
+///
+/// ```text
+/// [Work laptop]
+/// DELL U2412 = DisplayPort1
+/// LG HDR = HDMI1
+/// DELL U2412 : brightness = 30
+/// ```
+fn load_profiles() -> Vec<Profile> {
+    let contents = match std::fs::read_to_string("profiles.ini") {
+        Ok(contents) => contents,
+        Err(_) => return vec![],
+    };
+
+    parse_profiles(&contents)
+}
+
+fn parse_profiles(contents: &str) -> Vec<Profile> {
+    let mut profiles: Vec<Profile> = vec![];
+
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            let name = line[1..line.len() - 1].trim().to_string();
+
+            profiles.push(Profile {
+                name,
+                inputs: HashMap::new(),
+                brightness: HashMap::new(),
+            });
+
+            continue;
+        }
+
+        let mut parts = line.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let value = match parts.next() {
+            Some(value) => value.trim(),
+            None => continue,
+        };
+
+        let profile = match profiles.last_mut() {
+            Some(profile) => profile,
+            None => continue,
+        };
+
+        if let Some(idx) = key.rfind(':') {
+            let monitor = key[..idx].trim();
+            let attribute = key[idx + 1..].trim();
+
+            if attribute.eq_ignore_ascii_case("brightness") {
+                if let Ok(level) = value.parse::<u32>() {
+                    profile.brightness.insert(monitor.to_string(), level);
+                }
+
+                continue;
+            }
+        }
+
+        if let Some(input) = MonitorInput::from_name(value) {
+            profile.inputs.insert(key.to_string(), input);
+        }
+    }
+
+    profiles
+}
+
 pub struct App {
     manager: MonitorManager,
     tray: systray::Application,
+    profiles: Vec<Profile>,
 }
 
 impl App {
@@ -26,21 +140,87 @@ impl App {
         let mut tray = systray::Application::new().unwrap();
 
         for monitor in &manager.monitors {
-            let caps = monitor.capabilities.as_ref().unwrap();
-            let display_type = &caps.display_model;
+            let header = if monitor.is_primary {
+                format!("{} (Primary)", monitor.name)
+            } else {
+                monitor.name.clone()
+            };
 
-            tray.add_menu_item(&display_type, |_| {}).unwrap();
+            tray.add_menu_item(&header, |_| {}).unwrap();
 
             tray.add_menu_separator().unwrap();
 
+            let active = monitor.current_input().ok();
+
             for input in &monitor.inputs {
-                tray.add_menu_item(&input.to_string(), move |_| {
-                    monitor.set_input(input.clone());
+                let is_active = active
+                    .map(|a| a as u8 == *input as u8)
+                    .unwrap_or(false);
+
+                let label = if is_active {
+                    format!("\u{2713} {}", input)
+                } else {
+                    format!("   {}", input)
+                };
+
+                tray.add_menu_item(&label, move |_| {
+                    monitor.set_input(input.clone()).ok();
                 })
                 .unwrap();
             }
 
+            for control in monitor.continuous_controls() {
+                tray.add_menu_item(&format!("{} +10", control), move |_| {
+                    monitor.nudge(control, 10).ok();
+                })
+                .unwrap();
+
+                tray.add_menu_item(&format!("{} -10", control), move |_| {
+                    monitor.nudge(control, -10).ok();
+                })
+                .unwrap();
+            }
+
+            let current_mode = manager.current_video_mode(&monitor.device_name);
+
+            for mode in manager.enumerate_video_modes(&monitor.device_name) {
+                let is_current = current_mode.as_ref() == Some(&mode);
+
+                let label = if is_current {
+                    format!("\u{2713} {}", mode)
+                } else {
+                    format!("   {}", mode)
+                };
+
+                let device_name = monitor.device_name.clone();
+
+                tray.add_menu_item(&label, move |_| {
+                    set_video_mode(&device_name, &mode).ok();
+                })
+                .unwrap();
+            }
+
+            tray.add_menu_separator().unwrap();
+        }
+
+        let profiles = load_profiles();
+
+        if !profiles.is_empty() {
             tray.add_menu_separator().unwrap();
+            tray.add_menu_item(&"Profiles".to_string(), |_| {}).unwrap();
+
+            for (index, profile) in profiles.iter().enumerate() {
+                let label = if index < 9 {
+                    format!("{} (Ctrl+Alt+{})", profile.name, index + 1)
+                } else {
+                    profile.name.clone()
+                };
+
+                tray.add_menu_item(&label, move |_| {
+                    profile.apply(&manager);
+                })
+                .unwrap();
+            }
         }
 
         tray.add_menu_item(&"Quit".to_string(), |window| {
@@ -48,12 +228,76 @@ impl App {
         })
         .unwrap();
 
-        let app = App { manager, tray };
+        let app = App {
+            manager,
+            tray,
+            profiles,
+        };
 
         Ok(app)
     }
 
+    /// Spawn a dedicated thread that owns the global hotkeys and their
+    /// message loop. `RegisterHotKey` delivers `WM_HOTKEY` to the thread
+    /// that registered it, so registration and the pump live together
+    /// here rather than on the main thread — which must stay free for the
+    /// tray's own `wait_for_message` lifecycle.
+    ///
+    /// The thread builds its own `MonitorManager` so no winapi monitor
+    /// handles cross threads; it is detached and dies with the process
+    /// when `run` returns on Quit.
+    fn spawn_hotkey_listener(&self) {
+        if self.profiles.is_empty() {
+            return;
+        }
+
+        let profiles = self.profiles.clone();
+
+        std::thread::spawn(move || {
+            let manager = match MonitorManager::new() {
+                Ok(manager) => manager,
+                Err(_) => return,
+            };
+
+            for index in 0..profiles.len().min(9) {
+                let registered = unsafe {
+                    RegisterHotKey(
+                        std::ptr::null_mut(),
+                        index as i32,
+                        (MOD_CONTROL | MOD_ALT | MOD_NOREPEAT) as u32,
+                        (b'1' + index as u8) as u32,
+                    )
+                };
+
+                if registered == 0 {
+                    eprintln!(
+                        "Failed to register Ctrl+Alt+{} for profile \"{}\" (already in use?)",
+                        index + 1,
+                        profiles[index].name
+                    );
+                }
+            }
+
+            unsafe {
+                let mut msg: MSG = std::mem::zeroed();
+
+                while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+                    if msg.message == WM_HOTKEY {
+                        if let Some(profile) = profiles.get(msg.wParam as usize) {
+                            profile.apply(&manager);
+                        }
+                    }
+
+                    TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+            }
+        });
+    }
+
     pub fn run(&mut self) -> Result<(), AppError> {
+        self.spawn_hotkey_listener();
+
         let tray = &mut self.tray;
 
         tray.wait_for_message();